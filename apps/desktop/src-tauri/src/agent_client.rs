@@ -0,0 +1,178 @@
+// Async client for the agent's `/system/summary` endpoint, plus a background
+// poller that keeps the tray icon/tooltip reflecting live agent health.
+// Replaces the old "status" handler, which shelled out to
+// `powershell.exe -Command Invoke-RestMethod` and blocked the UI thread.
+
+use crate::platform::ServiceController;
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::tray::TrayIcon;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_dialog::DialogExt;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Most recently polled summary, managed as app state so the "Status" menu
+/// item can show it instantly instead of blocking on a fresh HTTP round trip.
+#[derive(Default)]
+pub struct LastSummary(pub Mutex<Option<SystemSummary>>);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SystemSummary {
+  #[serde(rename = "devicesConnected")]
+  pub devices_connected: u32,
+  #[serde(rename = "defaultDbOk")]
+  pub default_db_ok: bool,
+  #[serde(rename = "jobsRunning")]
+  pub jobs_running: u32,
+}
+
+pub struct AgentClient {
+  http: reqwest::Client,
+}
+
+impl AgentClient {
+  pub fn new() -> Self {
+    Self { http: reqwest::Client::builder().timeout(Duration::from_secs(3)).build().unwrap_or_default() }
+  }
+
+  /// Reads port/token via `lockfile_port`/`secrets::get_agent_token`, sends the
+  /// `X-Agent-Token` header, and deserializes the response. `None` on any
+  /// failure (no lockfile, agent unreachable, bad JSON) — callers treat that
+  /// as "unreachable".
+  ///
+  /// The lockfile read and the keyring lookup are both blocking I/O (the
+  /// latter a blocking D-Bus round trip on Linux), so they run on
+  /// `spawn_blocking` rather than inline in this `async fn` — this poll runs
+  /// forever on a timer, and blocking the tokio runtime on every tick is the
+  /// same UI-thread-stall problem this client was written to get rid of.
+  pub async fn fetch_summary(&self) -> Option<SystemSummary> {
+    let (port, token) = tokio::task::spawn_blocking(|| (crate::lockfile_port(), crate::secrets::get_agent_token()))
+      .await
+      .ok()?;
+    let url = format!("http://127.0.0.1:{}/system/summary", port?);
+    let mut req = self.http.get(url);
+    if let Some(token) = token {
+      req = req.header("X-Agent-Token", token);
+    }
+    req.send().await.ok()?.json::<SystemSummary>().await.ok()
+  }
+}
+
+enum Health {
+  Healthy,
+  Partial,
+  Unreachable,
+}
+
+impl Health {
+  fn from_summary(summary: &Option<SystemSummary>) -> Self {
+    match summary {
+      Some(s) if s.default_db_ok && s.jobs_running > 0 => Health::Healthy,
+      Some(_) => Health::Partial,
+      None => Health::Unreachable,
+    }
+  }
+
+  fn icon_bytes(&self) -> &'static [u8] {
+    match self {
+      Health::Healthy => include_bytes!("../icons/tray-green.png"),
+      Health::Partial => include_bytes!("../icons/tray-amber.png"),
+      Health::Unreachable => include_bytes!("../icons/tray-red.png"),
+    }
+  }
+}
+
+/// When the HTTP summary is unreachable because the vault is passphrase-
+/// locked, says so directly (see `unlock_prompt_if_locked`); otherwise falls
+/// back to the service status string so the tooltip can still say
+/// *something* about the service (e.g. that it isn't running at all)
+/// instead of just "unreachable".
+///
+/// `locked`/`status` are passed in rather than fetched here — both come from
+/// blocking calls (`secrets::is_locked` is a keyring round trip, the service
+/// status forks a subprocess), so `poll_loop` computes them via
+/// `spawn_blocking` once per tick instead of every caller re-deriving them
+/// inline.
+pub fn tooltip_for(summary: &Option<SystemSummary>, locked: bool, status: Option<&str>) -> String {
+  match summary {
+    Some(s) => format!(
+      "Devices: {}\nDefault DB: {}\nJobs running: {}",
+      s.devices_connected,
+      if s.default_db_ok { "OK" } else { "Not OK" },
+      s.jobs_running
+    ),
+    None if locked => "Agent token vault is locked".to_string(),
+    None => match status {
+      Some(status) => format!("Agent unreachable\n{}", status.trim()),
+      None => "Agent unreachable".to_string(),
+    },
+  }
+}
+
+/// Surfaces a `DialogExt` prompt the first time the vault is found locked
+/// (and resets so it'll prompt again if it becomes locked again later, e.g.
+/// after the user quits without unlocking). The dialog itself can only
+/// confirm/dismiss — it opens the main window, where the passphrase field
+/// lives, rather than collecting the passphrase itself.
+fn unlock_prompt_if_locked(app: &AppHandle<Wry>, locked: bool) {
+  static PROMPTED: AtomicBool = AtomicBool::new(false);
+  if !locked {
+    PROMPTED.store(false, Ordering::SeqCst);
+    return;
+  }
+  if PROMPTED.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  let app = app.clone();
+  app
+    .dialog()
+    .message("The agent token vault is locked. Open PLC Logger to enter your vault passphrase.")
+    .title("Vault locked")
+    .ok_button_label("Open")
+    .cancel_button_label("Later")
+    .show(move |open| {
+      if open {
+        if let Some(w) = app.get_webview_window("main") {
+          let _ = w.show();
+          let _ = w.set_focus();
+        }
+      }
+    });
+}
+
+fn apply(tray: &TrayIcon, summary: &Option<SystemSummary>, locked: bool, status: Option<&str>) {
+  let health = Health::from_summary(summary);
+  if let Ok(icon) = tauri::image::Image::from_bytes(health.icon_bytes()) {
+    let _ = tray.set_icon(Some(icon));
+  }
+  let _ = tray.set_tooltip(Some(tooltip_for(summary, locked, status)));
+}
+
+/// Runs until the app exits, refreshing the tray every `POLL_INTERVAL`.
+pub async fn poll_loop(app: AppHandle<Wry>, tray: TrayIcon) {
+  let client = AgentClient::new();
+  loop {
+    let summary = client.fetch_summary().await;
+    let need_status = summary.is_none();
+    // `is_locked` (keyring/D-Bus) and `ServiceController::status` (forks
+    // sc.exe/systemctl/launchctl) are both blocking; only fetch the service
+    // status when it'll actually be shown, but always run both off-thread so
+    // this timer tick never blocks a tokio worker on external-process I/O.
+    let (locked, status) = tokio::task::spawn_blocking(move || {
+      let locked = crate::secrets::is_locked();
+      let status = if need_status { crate::platform::controller().status() } else { None };
+      (locked, status)
+    })
+    .await
+    .unwrap_or((false, None));
+    apply(&tray, &summary, locked, status.as_deref());
+    unlock_prompt_if_locked(&app, locked);
+    if let Some(state) = app.try_state::<LastSummary>() {
+      *state.0.lock().unwrap() = summary;
+    }
+    tokio::time::sleep(POLL_INTERVAL).await;
+  }
+}
@@ -0,0 +1,149 @@
+// Launch-at-login registration, mirroring `platform`'s per-OS split: each
+// platform gets its own registration mechanism (Windows Run key, Linux
+// autostart .desktop file, macOS LaunchAgent), and the tray toggle always
+// reflects the real OS state rather than a stored preference that could
+// drift from it.
+
+const APP_NAME: &str = "PLCLogger";
+
+#[cfg(target_os = "windows")]
+mod imp {
+  use super::APP_NAME;
+  use winreg::enums::{HKEY_CURRENT_USER, KEY_ALL_ACCESS};
+  use winreg::RegKey;
+
+  fn run_key() -> std::io::Result<RegKey> {
+    RegKey::predef(HKEY_CURRENT_USER).open_subkey_with_flags(
+      r"Software\Microsoft\Windows\CurrentVersion\Run",
+      KEY_ALL_ACCESS,
+    )
+  }
+
+  pub fn is_enabled() -> bool {
+    run_key().and_then(|k| k.get_value::<String, _>(APP_NAME)).is_ok()
+  }
+
+  pub fn set_enabled(enabled: bool) -> std::io::Result<()> {
+    let key = run_key()?;
+    if enabled {
+      let exe = std::env::current_exe()?;
+      key.set_value(APP_NAME, &format!("\"{}\"", exe.display()))?;
+    } else {
+      let _ = key.delete_value(APP_NAME);
+    }
+    Ok(())
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+  use super::APP_NAME;
+  use std::path::PathBuf;
+
+  fn desktop_file() -> PathBuf {
+    let mut p = std::env::var_os("XDG_CONFIG_HOME")
+      .map(PathBuf::from)
+      .unwrap_or_else(|| {
+        let mut home = PathBuf::from(std::env::var_os("HOME").unwrap_or_default());
+        home.push(".config");
+        home
+      });
+    p.push("autostart/plc-logger.desktop");
+    p
+  }
+
+  pub fn is_enabled() -> bool {
+    // Desktop environments (e.g. GNOME Tweaks) disable autostart in place by
+    // flipping X-GNOME-Autostart-enabled to false rather than deleting the
+    // file, so mere existence isn't enough to trust.
+    match std::fs::read_to_string(desktop_file()) {
+      Ok(contents) => !contents.lines().any(|l| l.trim() == "X-GNOME-Autostart-enabled=false"),
+      Err(_) => false,
+    }
+  }
+
+  pub fn set_enabled(enabled: bool) -> std::io::Result<()> {
+    let path = desktop_file();
+    if enabled {
+      if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+      }
+      let exe = std::env::current_exe()?;
+      let contents = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\"\nX-GNOME-Autostart-enabled=true\n",
+        APP_NAME,
+        exe.to_string_lossy().replace('"', "\\\"")
+      );
+      std::fs::write(path, contents)?;
+    } else if path.exists() {
+      std::fs::remove_file(path)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+  use std::path::PathBuf;
+
+  fn plist_path() -> PathBuf {
+    let mut p = PathBuf::from(std::env::var_os("HOME").unwrap_or_default());
+    p.push("Library/LaunchAgents/com.plclogger.agent.plist");
+    p
+  }
+
+  fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+  }
+
+  pub fn is_enabled() -> bool {
+    plist_path().exists()
+  }
+
+  pub fn set_enabled(enabled: bool) -> std::io::Result<()> {
+    let path = plist_path();
+    if enabled {
+      if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+      }
+      let exe = std::env::current_exe()?;
+      let contents = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key><string>com.plclogger.agent</string>
+  <key>ProgramArguments</key><array><string>{}</string></array>
+  <key>RunAtLoad</key><true/>
+</dict>
+</plist>
+"#,
+        escape_xml(&exe.to_string_lossy())
+      );
+      std::fs::write(path, contents)?;
+    } else if path.exists() {
+      std::fs::remove_file(path)?;
+    }
+    Ok(())
+  }
+}
+
+pub fn is_autostart_enabled() -> bool { imp::is_enabled() }
+
+pub fn set_autostart(enabled: bool) -> std::io::Result<()> { imp::set_enabled(enabled) }
+
+/// Gated the same way as `read_lockfile`/`get_agent_token_cmd`: a window
+/// showing untrusted content has no business toggling OS-level login
+/// persistence for this app.
+#[tauri::command]
+pub fn is_autostart_enabled_cmd(window: tauri::Window) -> bool {
+  crate::ipc_guard::is_trusted_window(&window) && is_autostart_enabled()
+}
+
+#[tauri::command]
+pub fn set_autostart_cmd(window: tauri::Window, enabled: bool) -> Result<(), String> {
+  if !crate::ipc_guard::is_trusted_window(&window) {
+    return Err("origin not allowed".into());
+  }
+  set_autostart(enabled).map_err(|e| e.to_string())
+}
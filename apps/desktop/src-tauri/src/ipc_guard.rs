@@ -0,0 +1,41 @@
+// Origin allowlist for IPC commands that hand back secrets (the agent port
+// and token). The webview only ever loads the bundled app, but if it were
+// ever navigated to remote content, commands gated here refuse to answer.
+
+use tauri::Url;
+
+/// True for the bundled app's own origin (`tauri://` / `asset://` on desktop,
+/// `https://tauri.localhost` on Windows), false for any other `http(s)://`
+/// origin a window might have been navigated to.
+pub fn is_trusted_origin(url: &Url) -> bool {
+  match url.scheme() {
+    "tauri" | "asset" => true,
+    "https" => url.host_str() == Some("tauri.localhost"),
+    _ => false,
+  }
+}
+
+/// The single codepath every secret-returning/state-mutating IPC command
+/// should call to decide whether its calling window is allowed to invoke it.
+/// `Window::url()` fails closed: a window whose URL can't be read is treated
+/// the same as an untrusted one.
+pub fn is_trusted_window(window: &tauri::Window) -> bool {
+  window.url().map(|u| is_trusted_origin(&u)).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn allows_the_bundled_app_origin() {
+    assert!(is_trusted_origin(&Url::parse("tauri://localhost/index.html").unwrap()));
+    assert!(is_trusted_origin(&Url::parse("https://tauri.localhost/index.html").unwrap()));
+  }
+
+  #[test]
+  fn denies_remote_origins() {
+    assert!(!is_trusted_origin(&Url::parse("https://example.com/").unwrap()));
+    assert!(!is_trusted_origin(&Url::parse("http://127.0.0.1:8080/").unwrap()));
+  }
+}
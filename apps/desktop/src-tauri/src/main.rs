@@ -1,53 +1,75 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::process::Command;
 use tauri::{Manager, AppHandle, Wry};
 use tauri::tray::TrayIconBuilder;
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
+use tauri::menu::{CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder};
 use tauri_plugin_dialog::DialogExt;
 
-fn open_logs() {
-  if let Some(pd) = std::env::var_os("ProgramData") {
-    let mut p = std::path::PathBuf::from(pd); p.push("PLCLogger\\agent\\logs");
-    let _ = Command::new("explorer.exe").arg(p).spawn();
-  }
-}
+mod agent_client;
+mod autostart;
+mod ipc_guard;
+mod platform;
+mod secrets;
+mod updater;
 
-fn open_data() {
-  if let Some(pd) = std::env::var_os("ProgramData") {
-    let mut p = std::path::PathBuf::from(pd); p.push("PLCLogger\\agent");
-    let _ = Command::new("explorer.exe").arg(p).spawn();
-  }
-}
+use platform::ServiceController;
 
-// Elevate to control the Windows service installed by MSI (PLCLoggerSvc)
-fn start_service() { let _ = Command::new("powershell.exe").args(["-NoProfile","-Command", "Start-Process sc.exe -Verb runAs -ArgumentList 'start PLCLoggerSvc'"]).spawn(); }
-fn stop_service()  { let _ = Command::new("powershell.exe").args(["-NoProfile","-Command", "Start-Process sc.exe -Verb runAs -ArgumentList 'stop PLCLoggerSvc'"]).spawn(); }
+fn open_logs() { platform::open_folder(&platform::controller().logs_dir()); }
+fn open_data() { platform::open_folder(&platform::controller().data_dir()); }
+fn start_service() { platform::controller().start(); }
+fn stop_service() { platform::controller().stop(); }
 
 fn show_main(app: &AppHandle<Wry>) { if let Some(w) = app.get_webview_window("main") { let _ = w.show(); let _ = w.set_focus(); } }
 
+fn lockfile_path() -> Option<std::path::PathBuf> {
+  let mut p = platform::controller().data_dir();
+  p.push("agent.lock.json");
+  Some(p)
+}
+
+/// Returns the agent's listening port. The auth token no longer travels
+/// through this function: it's migrated out of the JSON file into the OS
+/// credential vault on first read, and callers fetch it via
+/// `secrets::get_agent_token` instead. Used both by the `read_lockfile`
+/// IPC command and internally (e.g. `agent_client`), which isn't subject to
+/// the IPC origin check since it never crosses the webview boundary.
+pub(crate) fn lockfile_port() -> Option<u16> {
+  let p = lockfile_path()?;
+  if !p.exists() {
+    return None;
+  }
+  let _ = secrets::migrate_token_from_lockfile(&p);
+  let txt = std::fs::read_to_string(&p).ok()?;
+  let v: serde_json::Value = serde_json::from_str(&txt).ok()?;
+  let port = v.get("port").and_then(|x| x.as_u64()).unwrap_or(0) as u16;
+  if port != 0 { Some(port) } else { None }
+}
+
+/// IPC-facing wrapper around `lockfile_port`. Denied for any window whose
+/// current URL isn't the bundled app's own origin — see `ipc_guard` — since
+/// the port is still sensitive enough to withhold from remote/untrusted
+/// content that somehow ended up loaded in a window.
 #[tauri::command]
-fn read_lockfile() -> Option<(u16, String)> {
-  if let Some(pd) = std::env::var_os("ProgramData") {
-    let mut p = std::path::PathBuf::from(pd);
-    p.push("PLCLogger\\agent\\agent.lock.json");
-    if p.exists() {
-      if let Ok(txt) = std::fs::read_to_string(&p) {
-        if let Ok(v) = serde_json::from_str::<serde_json::Value>(&txt) {
-          let port = v.get("port").and_then(|x| x.as_u64()).unwrap_or(0) as u16;
-          let tok = v.get("token").and_then(|x| x.as_str()).unwrap_or("").to_string();
-          if port != 0 { return Some((port, tok)); }
-        }
-      }
-    }
+fn read_lockfile(window: tauri::Window) -> Option<u16> {
+  if !ipc_guard::is_trusted_window(&window) {
+    return None;
   }
-  None
+  lockfile_port()
 }
 
 fn main() {
   tauri::Builder::default()
     .plugin(tauri_plugin_dialog::init())
-    .invoke_handler(tauri::generate_handler![read_lockfile])
+    .plugin(tauri_plugin_updater::Builder::new().build())
+    .invoke_handler(tauri::generate_handler![
+      read_lockfile,
+      secrets::get_agent_token_cmd,
+      secrets::unlock_vault_cmd,
+      secrets::set_vault_passphrase_cmd,
+      autostart::is_autostart_enabled_cmd,
+      autostart::set_autostart_cmd,
+    ])
+    .manage(agent_client::LastSummary::default())
     .setup(|app| {
       // Build tray menu
       let open = MenuItemBuilder::with_id("open", "Open PLC Logger").build(app)?;
@@ -56,39 +78,47 @@ fn main() {
       let stop = MenuItemBuilder::with_id("stop", "Stop Agent Service").build(app)?;
       let logs = MenuItemBuilder::with_id("logs", "Open Logs Folder").build(app)?;
       let data = MenuItemBuilder::with_id("data", "Open Data Folder").build(app)?;
+      let check_updates = MenuItemBuilder::with_id("check_updates", "Check for Updates").build(app)?;
+      let autostart_item = CheckMenuItemBuilder::with_id("autostart", "Start on Login")
+        .checked(autostart::is_autostart_enabled())
+        .build(app)?;
       let quit = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
-      let menu = MenuBuilder::new(app).items(&[&open,&status,&start,&stop,&logs,&data,&quit]).build()?;
-      TrayIconBuilder::new()
+      let menu = MenuBuilder::new(app).items(&[&open,&status,&start,&stop,&logs,&data,&check_updates,&autostart_item,&quit]).build()?;
+      let autostart_item_for_toggle = autostart_item.clone();
+      let tray = TrayIconBuilder::new()
         .menu(&menu)
-        .on_menu_event(|app, event| {
+        .on_menu_event(move |app, event| {
           match event.id.as_ref() {
             "open" => show_main(app),
             "status" => {
-              // Read summary from agent and show in a native dialog
-              let ps = r#"
-                try {
-                  $lf = Join-Path $env:ProgramData "PLCLogger\agent\agent.lock.json";
-                  $d = Get-Content $lf | ConvertFrom-Json;
-                  $u = "http://127.0.0.1:" + $d.port + "/system/summary";
-                  $h = @{}; if ($d.token) { $h["X-Agent-Token"] = $d.token };
-                  $r = Invoke-RestMethod -Uri $u -Headers $h -Method GET;
-                  "Devices: $($r.devicesConnected)`nDefault DB: $((if($r.defaultDbOk){"OK"}else{"Not OK"}))`nJobs running: $($r.jobsRunning)"
-                } catch { "Status unavailable" }
-              "#;
-              match Command::new("powershell.exe").args(["-NoProfile","-Command", ps]).output() {
-                Ok(out) => { let msg = String::from_utf8_lossy(&out.stdout).to_string(); app.dialog().message(msg).show(|_|{}); },
-                Err(_) => { app.dialog().message("Status unavailable").show(|_|{}); }
-              }
+              // The tray icon/tooltip already reflect live health; this just
+              // surfaces the last polled summary in a dialog on demand. A
+              // one-off blocking keyring/subprocess call here is fine (it's
+              // user-invoked, not on the poll timer).
+              let summary = app.state::<agent_client::LastSummary>().0.lock().unwrap().clone();
+              let locked = secrets::is_locked();
+              let status = platform::controller().status();
+              app.dialog().message(agent_client::tooltip_for(&summary, locked, status.as_deref())).show(|_| {});
             },
             "start" => start_service(),
             "stop" => stop_service(),
             "logs" => open_logs(),
             "data" => open_data(),
+            "check_updates" => updater::check_now(app),
+            "autostart" => {
+              let enabled = !autostart::is_autostart_enabled();
+              if autostart::set_autostart(enabled).is_ok() {
+                let _ = autostart_item_for_toggle.set_checked(enabled);
+              }
+            },
             "quit" => { app.exit(0); },
             _ => {}
           }
         })
         .build(app)?;
+
+      tauri::async_runtime::spawn(agent_client::poll_loop(app.handle().clone(), tray));
+      updater::check_on_startup(&app.handle());
       Ok(())
     })
     .on_window_event(|window, event| {
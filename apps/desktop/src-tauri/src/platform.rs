@@ -0,0 +1,157 @@
+// Cross-platform control of the background logging service (`PLCLoggerSvc` on
+// Windows, a user unit on Linux, a launchd agent on macOS), plus the folders
+// the tray links out to. Each OS gets its own `ServiceController` impl so the
+// tray/menu code stays platform-agnostic.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+pub trait ServiceController {
+  fn start(&self);
+  fn stop(&self);
+  /// Human-readable status line, or `None` if the service state can't be read.
+  fn status(&self) -> Option<String>;
+  fn logs_dir(&self) -> PathBuf;
+  fn data_dir(&self) -> PathBuf;
+}
+
+#[cfg(target_os = "windows")]
+pub struct WindowsController;
+
+#[cfg(target_os = "windows")]
+impl ServiceController for WindowsController {
+  fn start(&self) {
+    let _ = Command::new("powershell.exe")
+      .args(["-NoProfile", "-Command", "Start-Process sc.exe -Verb runAs -ArgumentList 'start PLCLoggerSvc'"])
+      .spawn();
+  }
+
+  fn stop(&self) {
+    let _ = Command::new("powershell.exe")
+      .args(["-NoProfile", "-Command", "Start-Process sc.exe -Verb runAs -ArgumentList 'stop PLCLoggerSvc'"])
+      .spawn();
+  }
+
+  fn status(&self) -> Option<String> {
+    let out = Command::new("sc.exe").args(["query", "PLCLoggerSvc"]).output().ok()?;
+    Some(String::from_utf8_lossy(&out.stdout).to_string())
+  }
+
+  fn logs_dir(&self) -> PathBuf {
+    let mut p = PathBuf::from(std::env::var_os("ProgramData").unwrap_or_default());
+    p.push("PLCLogger\\agent\\logs");
+    p
+  }
+
+  fn data_dir(&self) -> PathBuf {
+    let mut p = PathBuf::from(std::env::var_os("ProgramData").unwrap_or_default());
+    p.push("PLCLogger\\agent");
+    p
+  }
+}
+
+#[cfg(target_os = "linux")]
+pub struct LinuxController;
+
+#[cfg(target_os = "linux")]
+impl ServiceController for LinuxController {
+  fn start(&self) {
+    if Command::new("systemctl").args(["--user", "start", "plc-logger-agent"]).status().map(|s| s.success()).unwrap_or(false) {
+      return;
+    }
+    let _ = Command::new("pkexec").args(["systemctl", "start", "plc-logger-agent"]).spawn();
+  }
+
+  fn stop(&self) {
+    if Command::new("systemctl").args(["--user", "stop", "plc-logger-agent"]).status().map(|s| s.success()).unwrap_or(false) {
+      return;
+    }
+    let _ = Command::new("pkexec").args(["systemctl", "stop", "plc-logger-agent"]).spawn();
+  }
+
+  fn status(&self) -> Option<String> {
+    let out = Command::new("systemctl").args(["--user", "status", "plc-logger-agent"]).output().ok()?;
+    Some(String::from_utf8_lossy(&out.stdout).to_string())
+  }
+
+  // `start`/`stop` try the unprivileged `--user` unit first and only fall
+  // back to the system-wide one via `pkexec`, so the primary model is a
+  // per-user agent — both dirs live under $XDG_DATA_HOME accordingly, not
+  // the root-owned /var/lib tree the system-wide unit would use.
+  fn logs_dir(&self) -> PathBuf {
+    let mut p = Self::base_dir();
+    p.push("logs");
+    p
+  }
+
+  fn data_dir(&self) -> PathBuf {
+    Self::base_dir()
+  }
+}
+
+#[cfg(target_os = "linux")]
+impl LinuxController {
+  fn base_dir() -> PathBuf {
+    let mut p = std::env::var_os("XDG_DATA_HOME")
+      .map(PathBuf::from)
+      .unwrap_or_else(|| {
+        let mut home = PathBuf::from(std::env::var_os("HOME").unwrap_or_default());
+        home.push(".local/share");
+        home
+      });
+    p.push("plc-logger/agent");
+    p
+  }
+}
+
+#[cfg(target_os = "macos")]
+pub struct MacController;
+
+#[cfg(target_os = "macos")]
+impl ServiceController for MacController {
+  fn start(&self) {
+    let _ = Command::new("launchctl").args(["start", "com.plclogger.agent"]).spawn();
+  }
+
+  fn stop(&self) {
+    let _ = Command::new("launchctl").args(["stop", "com.plclogger.agent"]).spawn();
+  }
+
+  fn status(&self) -> Option<String> {
+    let out = Command::new("launchctl").args(["list", "com.plclogger.agent"]).output().ok()?;
+    Some(String::from_utf8_lossy(&out.stdout).to_string())
+  }
+
+  fn logs_dir(&self) -> PathBuf {
+    let mut p = PathBuf::from(std::env::var_os("HOME").unwrap_or_default());
+    p.push("Library/Application Support/PLCLogger/agent/logs");
+    p
+  }
+
+  fn data_dir(&self) -> PathBuf {
+    let mut p = PathBuf::from(std::env::var_os("HOME").unwrap_or_default());
+    p.push("Library/Application Support/PLCLogger/agent");
+    p
+  }
+}
+
+/// Opens `path` in the platform's default file manager.
+pub fn open_folder(path: &std::path::Path) {
+  #[cfg(target_os = "windows")]
+  let _ = Command::new("explorer.exe").arg(path).spawn();
+
+  #[cfg(target_os = "linux")]
+  let _ = Command::new("xdg-open").arg(path).spawn();
+
+  #[cfg(target_os = "macos")]
+  let _ = Command::new("open").arg(path).spawn();
+}
+
+#[cfg(target_os = "windows")]
+pub fn controller() -> impl ServiceController { WindowsController }
+
+#[cfg(target_os = "linux")]
+pub fn controller() -> impl ServiceController { LinuxController }
+
+#[cfg(target_os = "macos")]
+pub fn controller() -> impl ServiceController { MacController }
@@ -0,0 +1,290 @@
+// Vault-backed storage for the agent auth token, replacing the plaintext
+// `token` field in agent.lock.json (Windows Credential Manager / secret-service
+// via the `keyring` crate, depending on platform).
+//
+// Optionally, the token can be wrapped with an Argon2id-derived key before it
+// goes into the vault, for defense in depth against anything that can read
+// the vault entry directly (e.g. another process running as the same user).
+// This is opt-in: a user who never calls `set_passphrase`/`set_vault_passphrase_cmd`
+// gets the plain vault-backed behavior, unchanged from before. The passphrase
+// itself is never persisted — only cached in memory for the life of the
+// process — so it's re-prompted for on every app restart.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use keyring::Entry;
+use rand::RngCore;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+const SERVICE_NAME: &str = "PLCLoggerSvc/agent-token";
+const KEYRING_USER: &str = "agent";
+
+/// Marks a vault value as Argon2id/AES-256-GCM sealed rather than plaintext,
+/// so `get_agent_token`/`is_locked` can tell the two apart without a second
+/// vault entry.
+const SEALED_PREFIX: &str = "sealed:";
+
+fn entry() -> keyring::Result<Entry> {
+  Entry::new(SERVICE_NAME, KEYRING_USER)
+}
+
+fn passphrase_cache() -> &'static Mutex<Option<String>> {
+  static CACHE: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+  CACHE.get_or_init(|| Mutex::new(None))
+}
+
+fn cached_passphrase() -> Option<String> {
+  passphrase_cache().lock().unwrap().clone()
+}
+
+/// Argon2id, 64 MiB / 3 iterations, tuned to be memory-hard against offline
+/// cracking of a stolen vault entry while staying under ~1s on desktop hardware.
+fn derive_key(passphrase: &str, salt: &[u8; 16]) -> [u8; 32] {
+  let params = Params::new(64 * 1024, 3, 1, Some(32)).expect("valid argon2 params");
+  let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+  let mut key = [0u8; 32];
+  argon2
+    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+    .expect("argon2 derivation");
+  key
+}
+
+/// `salt (16) || nonce (12) || ciphertext`, base64-encoded so it can live in a
+/// single vault string entry.
+fn seal(token: &str, passphrase: &str) -> String {
+  let mut salt = [0u8; 16];
+  rand::thread_rng().fill_bytes(&mut salt);
+  let mut nonce_bytes = [0u8; 12];
+  rand::thread_rng().fill_bytes(&mut nonce_bytes);
+
+  let key = derive_key(passphrase, &salt);
+  let cipher = Aes256Gcm::new_from_slice(&key).expect("32-byte key");
+  let nonce = Nonce::from_slice(&nonce_bytes);
+  let ct = cipher.encrypt(nonce, token.as_bytes()).expect("encryption");
+
+  let mut blob = Vec::with_capacity(16 + 12 + ct.len());
+  blob.extend_from_slice(&salt);
+  blob.extend_from_slice(&nonce_bytes);
+  blob.extend_from_slice(&ct);
+  base64::encode(blob)
+}
+
+fn unseal(blob: &str, passphrase: &str) -> Option<String> {
+  let raw = base64::decode(blob).ok()?;
+  if raw.len() < 28 {
+    return None;
+  }
+  let salt: [u8; 16] = raw[..16].try_into().ok()?;
+  let nonce_bytes = &raw[16..28];
+  let ct = &raw[28..];
+
+  let key = derive_key(passphrase, &salt);
+  let cipher = Aes256Gcm::new_from_slice(&key).ok()?;
+  let pt = cipher.decrypt(Nonce::from_slice(nonce_bytes), ct).ok()?;
+  String::from_utf8(pt).ok()
+}
+
+/// Reads the plaintext token out of `agent.lock.json`, if one is still there,
+/// stores it in the platform credential vault, and overwrites the JSON field
+/// so the secret no longer lives on disk. A no-op once migration has already
+/// happened (the field is already empty).
+///
+/// If a passphrase has already been cached (the user set one up before this
+/// lockfile was ever read), the token is Argon2id-sealed before it goes into
+/// the vault; otherwise it's stored as plain vault text, same as before
+/// passphrase support existed. Either way, the lockfile is only blanked after
+/// the vault write is confirmed to have succeeded — if the vault is
+/// unavailable (no secret-service/D-Bus session, a locked Credential
+/// Manager, etc.) the plaintext token is left in place and migration is
+/// retried the next time this is called, rather than deleting the only copy
+/// of the token.
+pub fn migrate_token_from_lockfile(lockfile: &Path) -> std::io::Result<()> {
+  let txt = std::fs::read_to_string(lockfile)?;
+  let mut v: serde_json::Value = serde_json::from_str(&txt)?;
+  let tok = v.get("token").and_then(|x| x.as_str()).unwrap_or("").to_string();
+  if tok.is_empty() {
+    return Ok(());
+  }
+
+  let to_store = match cached_passphrase() {
+    Some(p) => format!("{SEALED_PREFIX}{}", seal(&tok, &p)),
+    None => tok,
+  };
+  let stored = entry().and_then(|e| e.set_password(&to_store)).is_ok();
+  if !stored {
+    return Ok(());
+  }
+
+  if let Some(obj) = v.as_object_mut() {
+    obj.insert("token".into(), serde_json::Value::String(String::new()));
+  }
+  std::fs::write(lockfile, serde_json::to_string_pretty(&v)?)?;
+  Ok(())
+}
+
+/// Fetches the agent token from the vault. Returns `None` if nothing has been
+/// migrated yet, or if the stored entry is passphrase-sealed and `is_locked`
+/// would report true (no passphrase cached yet to unseal it with).
+pub fn get_agent_token() -> Option<String> {
+  let stored = entry().ok()?.get_password().ok()?;
+  match stored.strip_prefix(SEALED_PREFIX) {
+    Some(blob) => unseal(blob, &cached_passphrase()?),
+    None => Some(stored),
+  }
+}
+
+/// True when the vault holds a passphrase-sealed token but no passphrase has
+/// been supplied yet this run of the app. Callers (the tray poller, the
+/// "status" menu item) use this to prompt for the passphrase via `DialogExt`
+/// instead of just reporting "agent unreachable".
+pub fn is_locked() -> bool {
+  let Some(stored) = entry().ok().and_then(|e| e.get_password().ok()) else {
+    return false;
+  };
+  stored.starts_with(SEALED_PREFIX) && cached_passphrase().is_none()
+}
+
+/// Pure decision behind `unlock_with_passphrase`: does `passphrase` actually
+/// unseal what's currently stored in the vault? Split out from the keyring
+/// I/O so the wrong-passphrase case can be unit tested without a real vault.
+fn passphrase_unlocks(stored: &str, passphrase: &str) -> bool {
+  stored.strip_prefix(SEALED_PREFIX).and_then(|blob| unseal(blob, passphrase)).is_some()
+}
+
+/// Unlocks a sealed vault entry for this run of the app: verifies
+/// `passphrase` actually unseals the stored blob and, only on success,
+/// caches it in memory so `get_agent_token` can use it. Returns `false` (and
+/// leaves the cache untouched) if the entry isn't sealed or the passphrase
+/// is wrong, so a typo doesn't get remembered as "unlocked".
+pub fn unlock_with_passphrase(passphrase: &str) -> bool {
+  let Some(stored) = entry().ok().and_then(|e| e.get_password().ok()) else {
+    return false;
+  };
+  if !passphrase_unlocks(&stored, passphrase) {
+    return false;
+  }
+  *passphrase_cache().lock().unwrap() = Some(passphrase.to_string());
+  true
+}
+
+/// Pure decision behind `set_passphrase`: computes the re-sealed vault value
+/// for `stored`, or `None` if `stored` is already sealed and `current`
+/// doesn't unseal it. Split out from the keyring I/O for the same reason as
+/// `passphrase_unlocks`.
+fn reseal(stored: &str, new_passphrase: &str, current: Option<&str>) -> Option<String> {
+  let tok = match stored.strip_prefix(SEALED_PREFIX) {
+    Some(blob) => current.and_then(|p| unseal(blob, p))?,
+    None => stored.to_string(),
+  };
+  Some(format!("{SEALED_PREFIX}{}", seal(&tok, new_passphrase)))
+}
+
+/// Opts the current vault entry into passphrase sealing (or re-seals it
+/// under a new passphrase). If the entry is already sealed, `current` must
+/// unseal it first — this refuses to overwrite a sealed entry blind, the
+/// same way `unlock_with_passphrase` refuses to cache a wrong guess.
+pub fn set_passphrase(new_passphrase: &str, current: Option<&str>) -> bool {
+  let Ok(e) = entry() else {
+    return false;
+  };
+  let Ok(stored) = e.get_password() else {
+    return false;
+  };
+  let Some(sealed) = reseal(&stored, new_passphrase, current) else {
+    return false;
+  };
+  if e.set_password(&sealed).is_err() {
+    return false;
+  }
+  *passphrase_cache().lock().unwrap() = Some(new_passphrase.to_string());
+  true
+}
+
+/// Denied for any window whose current URL isn't the bundled app's own
+/// origin — see `crate::ipc_guard` — for the same reason `read_lockfile` is
+/// gated: this hands back the live agent auth token.
+#[tauri::command]
+pub fn get_agent_token_cmd(window: tauri::Window) -> Option<String> {
+  if !crate::ipc_guard::is_trusted_window(&window) {
+    return None;
+  }
+  get_agent_token()
+}
+
+/// Called by the app's passphrase prompt (surfaced via `DialogExt` when
+/// `is_locked()`) once the user types in the vault passphrase. Gated the
+/// same way as `get_agent_token_cmd`.
+#[tauri::command]
+pub fn unlock_vault_cmd(window: tauri::Window, passphrase: String) -> bool {
+  if !crate::ipc_guard::is_trusted_window(&window) {
+    return false;
+  }
+  unlock_with_passphrase(&passphrase)
+}
+
+/// Called from the (future) "Set vault passphrase" settings action. Gated
+/// the same way as `get_agent_token_cmd`.
+#[tauri::command]
+pub fn set_vault_passphrase_cmd(window: tauri::Window, new_passphrase: String, current_passphrase: Option<String>) -> bool {
+  if !crate::ipc_guard::is_trusted_window(&window) {
+    return false;
+  }
+  set_passphrase(&new_passphrase, current_passphrase.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn seal_unseal_round_trips() {
+    let blob = seal("super-secret-token", "correct horse");
+    assert_eq!(unseal(&blob, "correct horse").as_deref(), Some("super-secret-token"));
+  }
+
+  #[test]
+  fn unseal_rejects_wrong_passphrase() {
+    let blob = seal("super-secret-token", "correct horse");
+    assert_eq!(unseal(&blob, "wrong passphrase"), None);
+  }
+
+  #[test]
+  fn unseal_rejects_corrupted_blob() {
+    assert_eq!(unseal("not-base64!!", "any"), None);
+    assert_eq!(unseal(&base64::encode([0u8; 10]), "any"), None);
+  }
+
+  #[test]
+  fn derive_key_is_deterministic_per_salt() {
+    let salt = [7u8; 16];
+    assert_eq!(derive_key("pw", &salt), derive_key("pw", &salt));
+    assert_ne!(derive_key("pw", &salt), derive_key("pw", &[9u8; 16]));
+    assert_ne!(derive_key("pw", &salt), derive_key("other", &salt));
+  }
+
+  #[test]
+  fn passphrase_unlocks_only_the_sealed_entry_with_the_right_passphrase() {
+    let stored = format!("{SEALED_PREFIX}{}", seal("tok", "hunter2"));
+    assert!(passphrase_unlocks(&stored, "hunter2"));
+    assert!(!passphrase_unlocks(&stored, "wrong"));
+    assert!(!passphrase_unlocks("tok", "hunter2")); // not sealed at all
+  }
+
+  #[test]
+  fn reseal_plain_entry_needs_no_current_passphrase() {
+    let sealed = reseal("tok", "new-pass", None).expect("plaintext entries always reseal");
+    assert_eq!(unseal(sealed.strip_prefix(SEALED_PREFIX).unwrap(), "new-pass").as_deref(), Some("tok"));
+  }
+
+  #[test]
+  fn reseal_sealed_entry_requires_correct_current_passphrase() {
+    let stored = format!("{SEALED_PREFIX}{}", seal("tok", "old-pass"));
+    assert_eq!(reseal(&stored, "new-pass", None), None);
+    assert_eq!(reseal(&stored, "new-pass", Some("wrong")), None);
+
+    let resealed = reseal(&stored, "new-pass", Some("old-pass")).expect("correct current passphrase reseals");
+    assert_eq!(unseal(resealed.strip_prefix(SEALED_PREFIX).unwrap(), "new-pass").as_deref(), Some("tok"));
+  }
+}
@@ -0,0 +1,86 @@
+// Self-update for the tray/UI binary, built on the Tauri updater plugin. The
+// data-logging service (`PLCLoggerSvc`) is a separate process installed by
+// the MSI and is never touched here — only the tray binary is replaced.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Wry};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_updater::UpdaterExt;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn throttle_stamp_path() -> std::path::PathBuf {
+  let mut p = crate::platform::controller().data_dir();
+  p.push("last_update_check");
+  p
+}
+
+fn should_check_now() -> bool {
+  let p = throttle_stamp_path();
+  let last = std::fs::read_to_string(&p).ok().and_then(|s| s.trim().parse::<u64>().ok());
+  let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+  match last {
+    Some(last) => now.saturating_sub(last) >= CHECK_INTERVAL.as_secs(),
+    None => true,
+  }
+}
+
+fn record_check_now() {
+  let p = throttle_stamp_path();
+  let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+  let _ = std::fs::write(p, now.to_string());
+}
+
+/// Checks the release endpoint and, if a newer signed build is available,
+/// asks the user to confirm before downloading and installing it. The
+/// updater plugin verifies the Ed25519/minisign signature on the downloaded
+/// artifact itself before `download_and_install` is allowed to proceed.
+async fn check_and_prompt(app: &AppHandle<Wry>) {
+  let updater = match app.updater() {
+    Ok(u) => u,
+    Err(_) => return,
+  };
+
+  let update = match updater.check().await {
+    Ok(Some(update)) => update,
+    _ => return,
+  };
+
+  let changelog = update.body.clone().unwrap_or_else(|| "No changelog provided.".to_string());
+  let message = format!(
+    "PLC Logger {} is available (you have {}).\n\n{}\n\nThe PLCLoggerSvc data-logging service keeps running during this update.",
+    update.version, update.current_version, changelog
+  );
+
+  let app_for_install = app.clone();
+  app.dialog().message(message).title("Update available").ok_button_label("Install").cancel_button_label("Later").show(move |confirmed| {
+    if !confirmed {
+      return;
+    }
+    tauri::async_runtime::spawn(async move {
+      match update.download_and_install(|_chunk, _total| {}, || {}).await {
+        Ok(()) => app_for_install.restart(),
+        Err(e) => { app_for_install.dialog().message(format!("Update failed: {e}")).title("Update failed").show(|_| {}); },
+      }
+    });
+  });
+}
+
+/// Called once on startup; only actually hits the network if the last check
+/// was more than a day ago.
+pub fn check_on_startup(app: &AppHandle<Wry>) {
+  if !should_check_now() {
+    return;
+  }
+  record_check_now();
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move { check_and_prompt(&app).await });
+}
+
+/// Called from the "Check for Updates" tray menu item; always hits the
+/// network regardless of the daily throttle.
+pub fn check_now(app: &AppHandle<Wry>) {
+  record_check_now();
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move { check_and_prompt(&app).await });
+}